@@ -1,17 +1,26 @@
 // Conversion between machine integers.
 
-use std::{u8, u16, u32, u64, usize, i8, i16, i32, i64, isize};
+#[cfg(feature = "std")]
 use std::error::Error;
-use std::fmt::{self, Display, Formatter};
-use std::mem;
+use core::fmt::{self, Display, Formatter};
+use core::mem;
+use core::num::{NonZeroU8, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroUsize,
+                NonZeroI8, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroIsize};
+#[cfg(feature = "i128")]
+use core::num::{NonZeroU128, NonZeroI128};
 
 use {TryFrom, Void};
 
 /// Error which occurs when conversion from one integer type to another fails.
+///
+/// This is a leaf error with no wrapped cause, so there is no underlying
+/// context to preserve when `std` (and `Error::source`) is unavailable.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum TryFromIntError {
     Overflow,
     Underflow,
+    NotFinite,
+    Zero,
 }
 
 impl TryFromIntError {
@@ -19,16 +28,25 @@ impl TryFromIntError {
         match self {
             TryFromIntError::Overflow => "integer overflow",
             TryFromIntError::Underflow => "integer underflow",
+            TryFromIntError::NotFinite => "not a finite number",
+            TryFromIntError::Zero => "zero is not a valid nonzero integer",
         }
     }
 }
 
+impl From<Void> for TryFromIntError {
+    fn from(void: Void) -> TryFromIntError {
+        match void {}
+    }
+}
+
 impl Display for TryFromIntError {
     fn fmt(&self, n: &mut Formatter) -> fmt::Result {
         n.write_str(self.as_str())
     }
 }
 
+#[cfg(feature = "std")]
 impl Error for TryFromIntError {
     fn description(&self) -> &str {
         self.as_str()
@@ -57,6 +75,12 @@ impl_infallible! {
     isize from u8, u16, i8, i16, i32, isize;
 }
 
+#[cfg(feature = "i128")]
+impl_infallible! {
+    u128 from u8, u16, u32, u64, usize, u128;
+    i128 from u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, i128;
+}
+
 #[test]
 fn test_infallible() {
     assert_eq!(u64::try_from(usize::MAX), Ok(usize::MAX as u64));
@@ -85,6 +109,15 @@ impl_unsigned_from_unsigned! {
     usize from u64;
 }
 
+#[cfg(feature = "i128")]
+impl_unsigned_from_unsigned! {
+    u8 from u128;
+    u16 from u128;
+    u32 from u128;
+    u64 from u128;
+    usize from u128;
+}
+
 #[test]
 fn test_unsigned_from_unsigned() {
     assert_eq!(u8::try_from(0xffu16), Ok(0xffu8));
@@ -125,6 +158,16 @@ impl_unsigned_from_signed! {
     usize from i8, i16, i32, i64, isize;
 }
 
+#[cfg(feature = "i128")]
+impl_unsigned_from_signed! {
+    u8 from i128;
+    u16 from i128;
+    u32 from i128;
+    u64 from i128;
+    usize from i128;
+    u128 from i8, i16, i32, i64, isize, i128;
+}
+
 #[test]
 fn test_unsigned_from_signed() {
     assert_eq!(u8::try_from(0i16), Ok(0u8));
@@ -164,6 +207,16 @@ impl_signed_from_unsigned! {
     isize from u32, u64, usize;
 }
 
+#[cfg(feature = "i128")]
+impl_signed_from_unsigned! {
+    i8 from u128;
+    i16 from u128;
+    i32 from u128;
+    i64 from u128;
+    isize from u128;
+    i128 from u128;
+}
+
 #[test]
 fn test_signed_from_unsigned() {
     assert_eq!(i8::try_from(0x7fu8), Ok(0x7fi8));
@@ -207,6 +260,15 @@ impl_signed_from_signed! {
     isize from i64;
 }
 
+#[cfg(feature = "i128")]
+impl_signed_from_signed! {
+    i8 from i128;
+    i16 from i128;
+    i32 from i128;
+    i64 from i128;
+    isize from i128;
+}
+
 #[test]
 fn test_signed_from_signed() {
     assert_eq!(i8::try_from(127i16), Ok(127i8));
@@ -222,3 +284,214 @@ fn test_signed_from_signed() {
         assert!(isize::try_from(i64::MAX).unwrap() > 0x7fff_ffffisize);
     }
 }
+
+#[cfg(feature = "i128")]
+#[test]
+fn test_i128() {
+    assert_eq!(u64::try_from(u128::MAX), Err(TryFromIntError::Overflow));
+    assert_eq!(i128::try_from(u64::MAX), Ok(u64::MAX as i128));
+    assert_eq!(u128::try_from(-1i8), Err(TryFromIntError::Underflow));
+}
+
+// The float conversions lean on `trunc`/`powi`, which are provided by `std`
+// rather than `core`, so they are only available with the `std` feature.
+#[cfg(feature = "std")]
+macro_rules! impl_from_float {
+    { $($t:ident unsigned;)* @ $($s:ident signed;)* } => {
+        $(
+            impl_from_float!(@one $t, f32, unsigned);
+            impl_from_float!(@one $t, f64, unsigned);
+        )*
+        $(
+            impl_from_float!(@one $s, f32, signed);
+            impl_from_float!(@one $s, f64, signed);
+        )*
+    };
+    (@one $t:ident, $f:ident, unsigned) => {
+        impl TryFrom<$f> for $t {
+            type Err = TryFromIntError;
+
+            fn try_from (n: $f) -> Result<$t, TryFromIntError> {
+                if n.is_nan() || n.is_infinite() {
+                    return Err(TryFromIntError::NotFinite);
+                }
+                let bits = (mem::size_of::<$t>() * 8) as i32;
+                let trunc = n.trunc();
+                if trunc < 0.0 {
+                    Err(TryFromIntError::Underflow)
+                } else if trunc >= (2.0 as $f).powi(bits) {
+                    Err(TryFromIntError::Overflow)
+                } else {
+                    Ok(trunc as $t)
+                }
+            }
+        }
+    };
+    (@one $t:ident, $f:ident, signed) => {
+        impl TryFrom<$f> for $t {
+            type Err = TryFromIntError;
+
+            fn try_from (n: $f) -> Result<$t, TryFromIntError> {
+                if n.is_nan() || n.is_infinite() {
+                    return Err(TryFromIntError::NotFinite);
+                }
+                let bits = (mem::size_of::<$t>() * 8) as i32;
+                let trunc = n.trunc();
+                if trunc < -(2.0 as $f).powi(bits - 1) {
+                    Err(TryFromIntError::Underflow)
+                } else if trunc >= (2.0 as $f).powi(bits - 1) {
+                    Err(TryFromIntError::Overflow)
+                } else {
+                    Ok(trunc as $t)
+                }
+            }
+        }
+    };
+}
+
+#[cfg(feature = "std")]
+impl_from_float! {
+    u8 unsigned;
+    u16 unsigned;
+    u32 unsigned;
+    u64 unsigned;
+    usize unsigned;
+    @
+    i8 signed;
+    i16 signed;
+    i32 signed;
+    i64 signed;
+    isize signed;
+}
+
+#[cfg(all(feature = "std", feature = "i128"))]
+impl_from_float! {
+    u128 unsigned;
+    @
+    i128 signed;
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_from_float() {
+    // Truncation toward zero, boundary just below the next integer.
+    assert_eq!(u8::try_from(255.9f32), Ok(255u8));
+    assert_eq!(u8::try_from(256.0f32), Err(TryFromIntError::Overflow));
+    assert_eq!(i8::try_from(127.9f64), Ok(127i8));
+    assert_eq!(i8::try_from(-128.9f64), Ok(-128i8));
+    assert_eq!(i8::try_from(-129.0f64), Err(TryFromIntError::Underflow));
+
+    // Signed zero maps to zero, negatives to an unsigned target underflow.
+    assert_eq!(u8::try_from(-0.0f32), Ok(0u8));
+    assert_eq!(u8::try_from(-1.0f32), Err(TryFromIntError::Underflow));
+
+    // Non-finite inputs are rejected before any range check.
+    assert_eq!(u32::try_from(f32::NAN), Err(TryFromIntError::NotFinite));
+    assert_eq!(
+        u64::try_from(f64::INFINITY),
+        Err(TryFromIntError::NotFinite)
+    );
+
+    // The max of a 64-bit type is not representable in f64, so the bound is
+    // checked against the next power of two rather than `MAX` itself.
+    assert_eq!(u64::try_from(2.0f64.powi(64)), Err(TryFromIntError::Overflow));
+    assert!(u64::try_from(2.0f64.powi(63)).is_ok());
+}
+
+macro_rules! nonzero {
+    (u8)    => { NonZeroU8 };
+    (u16)   => { NonZeroU16 };
+    (u32)   => { NonZeroU32 };
+    (u64)   => { NonZeroU64 };
+    (usize) => { NonZeroUsize };
+    (i8)    => { NonZeroI8 };
+    (i16)   => { NonZeroI16 };
+    (i32)   => { NonZeroI32 };
+    (i64)   => { NonZeroI64 };
+    (isize) => { NonZeroIsize };
+    (u128)  => { NonZeroU128 };
+    (i128)  => { NonZeroI128 };
+}
+
+macro_rules! impl_nonzero {
+    { $($t:ident from $($f:ident),*;)* } => { $($(
+        // A nonzero source unwraps to its inner value and reuses the plain
+        // conversion, so it is exactly as fallible as that conversion.
+        impl TryFrom<nonzero!($f)> for $t {
+            type Err = <$t as TryFrom<$f>>::Err;
+            fn try_from (n: nonzero!($f)) -> Result<$t, Self::Err> {
+                <$t as TryFrom<$f>>::try_from(n.get())
+            }
+        }
+
+        // A nonzero target additionally rejects a zero input once the value
+        // is known to be in range.
+        impl TryFrom<$f> for nonzero!($t) {
+            type Err = TryFromIntError;
+            fn try_from (n: $f) -> Result<nonzero!($t), TryFromIntError> {
+                let value = <$t as TryFrom<$f>>::try_from(n).map_err(TryFromIntError::from)?;
+                <nonzero!($t)>::new(value).ok_or(TryFromIntError::Zero)
+            }
+        }
+
+        // Nonzero to nonzero is range-checked but never zero-failing, since a
+        // nonzero input keeps a nonzero value through the conversion.
+        impl TryFrom<nonzero!($f)> for nonzero!($t) {
+            type Err = <$t as TryFrom<$f>>::Err;
+            fn try_from (n: nonzero!($f)) -> Result<nonzero!($t), Self::Err> {
+                let value = <$t as TryFrom<$f>>::try_from(n.get())?;
+                Ok(<nonzero!($t)>::new(value).unwrap())
+            }
+        }
+    )*)* };
+}
+
+impl_nonzero! {
+    u8 from u8, u16, u32, u64, usize, i8, i16, i32, i64, isize;
+    u16 from u8, u16, u32, u64, usize, i8, i16, i32, i64, isize;
+    u32 from u8, u16, u32, u64, usize, i8, i16, i32, i64, isize;
+    u64 from u8, u16, u32, u64, usize, i8, i16, i32, i64, isize;
+    usize from u8, u16, u32, u64, usize, i8, i16, i32, i64, isize;
+    i8 from u8, u16, u32, u64, usize, i8, i16, i32, i64, isize;
+    i16 from u8, u16, u32, u64, usize, i8, i16, i32, i64, isize;
+    i32 from u8, u16, u32, u64, usize, i8, i16, i32, i64, isize;
+    i64 from u8, u16, u32, u64, usize, i8, i16, i32, i64, isize;
+    isize from u8, u16, u32, u64, usize, i8, i16, i32, i64, isize;
+}
+
+#[cfg(feature = "i128")]
+impl_nonzero! {
+    u128 from u8, u16, u32, u64, usize, u128, i8, i16, i32, i64, isize, i128;
+    i128 from u8, u16, u32, u64, usize, u128, i8, i16, i32, i64, isize, i128;
+    u8 from u128, i128;
+    u16 from u128, i128;
+    u32 from u128, i128;
+    u64 from u128, i128;
+    usize from u128, i128;
+    i8 from u128, i128;
+    i16 from u128, i128;
+    i32 from u128, i128;
+    i64 from u128, i128;
+    isize from u128, i128;
+}
+
+#[test]
+fn test_nonzero() {
+    let big = NonZeroU16::new(0x100).unwrap();
+    assert_eq!(u8::try_from(big), Err(TryFromIntError::Overflow));
+
+    let small = NonZeroU16::new(0xff).unwrap();
+    assert_eq!(u8::try_from(small), Ok(0xffu8));
+
+    // Zero is the only value a nonzero target rejects outright.
+    assert_eq!(NonZeroU8::try_from(0u16), Err(TryFromIntError::Zero));
+    assert_eq!(NonZeroU8::try_from(1u16), Ok(NonZeroU8::new(1).unwrap()));
+    assert_eq!(NonZeroU8::try_from(0x100u16), Err(TryFromIntError::Overflow));
+
+    // Nonzero to nonzero never produces the zero error.
+    assert_eq!(
+        NonZeroU8::try_from(small),
+        Ok(NonZeroU8::new(0xff).unwrap())
+    );
+    assert_eq!(NonZeroU8::try_from(big), Err(TryFromIntError::Overflow));
+}
@@ -0,0 +1,39 @@
+//! Attempted conversions between types which may fail in a controlled way.
+//!
+//! This provides `TryFrom`/`TryInto` traits in the shape of RFC 1542,
+//! together with fallible conversions between the machine integer types.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+// Under `#![no_std]` `core` is in scope implicitly; with the `std` feature on
+// that attribute is inactive, so on edition 2015 it must be linked by hand.
+#[cfg(feature = "std")]
+extern crate core;
+
+mod int;
+
+pub use int::TryFromIntError;
+
+/// Attempt a conversion that may fail, consuming the input value.
+pub trait TryFrom<T>: Sized {
+    type Err;
+    fn try_from(t: T) -> Result<Self, Self::Err>;
+}
+
+/// The reciprocal of `TryFrom`: attempt a conversion that consumes `self`,
+/// mirroring the `From`/`Into` pair in the standard library.
+pub trait TryInto<T>: Sized {
+    type Err;
+    fn try_into(self) -> Result<T, Self::Err>;
+}
+
+impl<T, U> TryInto<U> for T where U: TryFrom<T> {
+    type Err = U::Err;
+    fn try_into(self) -> Result<U, U::Err> {
+        U::try_from(self)
+    }
+}
+
+/// A type with no values, used as the error of an infallible conversion.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Void {}